@@ -0,0 +1,121 @@
+// Parallel benchmark harness: runs many independent auto-solves across
+// worker threads and aggregates the results into a single report, so a
+// strategy can be validated over millions of randomized puzzles quickly
+// instead of reading through a giant `Vec<GameResult>`.
+
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::error::Result;
+use crate::{auto_solve, strategy, GameResult};
+
+#[derive(Debug)]
+pub struct BenchReport {
+    pub runs: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub wall_time: Duration,
+    pub min_time: Duration,
+    pub max_time: Duration,
+    pub mean_time: Duration,
+}
+
+impl BenchReport {
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.runs as f64
+    }
+
+    pub fn throughput(&self) -> f64 {
+        self.runs as f64 / self.wall_time.as_secs_f64()
+    }
+}
+
+struct PartialTally {
+    wins: usize,
+    losses: usize,
+    total_time: Duration,
+    min_time: Duration,
+    max_time: Duration,
+    count: usize,
+}
+
+impl PartialTally {
+    fn new() -> Self {
+        PartialTally {
+            wins: 0,
+            losses: 0,
+            total_time: Duration::ZERO,
+            min_time: Duration::MAX,
+            max_time: Duration::ZERO,
+            count: 0,
+        }
+    }
+
+    fn record(&mut self, result: GameResult, elapsed: Duration) {
+        match result {
+            GameResult::Win => self.wins += 1,
+            GameResult::Lose => self.losses += 1,
+        }
+        self.total_time += elapsed;
+        self.min_time = self.min_time.min(elapsed);
+        self.max_time = self.max_time.max(elapsed);
+        self.count += 1;
+    }
+
+    fn merge(mut self, other: PartialTally) -> PartialTally {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.total_time += other.total_time;
+        self.min_time = self.min_time.min(other.min_time);
+        self.max_time = self.max_time.max(other.max_time);
+        self.count += other.count;
+        self
+    }
+}
+
+/// Run `attempts` independent, non-verbose auto-solves spread across
+/// `num_cpus::get()` worker threads and return an aggregated report.
+/// Each run draws its masses from its worker thread's own RNG, so
+/// splitting the work across threads doesn't affect correctness.
+pub fn run(attempts: usize, n_masses: usize, n_weighings: usize) -> Result<BenchReport> {
+    // Generate the strategy once up front instead of once per attempt -
+    // it's by far the most expensive part of a solve, and every attempt
+    // walks the same tree against a freshly randomized set of masses.
+    let tree = strategy::StrategyTree::generate(n_masses, n_weighings)?;
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_cpus::get())
+        .build()
+        .expect("failed to build rayon thread pool");
+
+    let wall_clock_start = Instant::now();
+
+    let tally = pool.install(|| {
+        (0..attempts)
+            .into_par_iter()
+            .fold(PartialTally::new, |mut tally, _| {
+                let start = Instant::now();
+                let mut rng = rand::thread_rng();
+                let (result, _transcript) = auto_solve(&tree, false, n_masses, n_weighings, &mut rng)
+                    .expect("(masses, weighings) already validated above");
+                tally.record(result, start.elapsed());
+                tally
+            })
+            .reduce(PartialTally::new, PartialTally::merge)
+    });
+
+    Ok(BenchReport {
+        runs: tally.count,
+        wins: tally.wins,
+        losses: tally.losses,
+        wall_time: wall_clock_start.elapsed(),
+        min_time: tally.min_time,
+        max_time: tally.max_time,
+        mean_time: if tally.count > 0 {
+            tally.total_time / tally.count as u32
+        } else {
+            Duration::ZERO
+        },
+    })
+}
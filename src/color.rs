@@ -0,0 +1,58 @@
+// Terminal color/styling support, shared by every print site so manual,
+// auto and bench output stay visually consistent.
+//
+// Gated behind `--color auto/always/never`: "auto" (the default) stays
+// plain when stdout isn't a terminal, so piped or redirected output isn't
+// full of escape codes.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use colored::{Color, Colorize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_mode(mode: ColorMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+fn mode() -> ColorMode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => ColorMode::Always,
+        2 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    }
+}
+
+fn enabled() -> bool {
+    match mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => std::io::stdout().is_terminal(),
+    }
+}
+
+/// Style `text` in `color`, unless color output is disabled.
+pub fn style(text: &str, color: Color) -> String {
+    if enabled() {
+        text.color(color).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Dim `text`, unless color output is disabled.
+pub fn dim(text: &str) -> String {
+    if enabled() {
+        text.dimmed().to_string()
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,37 @@
+// Crate-wide error type. Functions whose failure modes are caused by bad
+// input or an impossible configuration return `Result<_, PuzzleError>`
+// instead of panicking, so a bad REPL line re-prompts instead of
+// crashing the whole program.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PuzzleError {
+    #[error("'{0}' isn't the name of any mass in play")]
+    UnknownMass(char),
+
+    #[error("a pan can't be empty")]
+    EmptyPan,
+
+    #[error("pans must hold the same number of masses (left has {left}, right has {right})")]
+    UnequalPans { left: usize, right: usize },
+
+    #[error(
+        "{n_masses} masses can't be told apart in {n_weighings} weighings (need 2 * masses <= 3^weighings)"
+    )]
+    NoStrategy { n_masses: usize, n_weighings: usize },
+
+    #[error("{0} masses requested, but masses are named A..Z so at most 26 are supported")]
+    TooManyMasses(usize),
+
+    #[error("at least 1 mass is required")]
+    TooFewMasses,
+
+    #[error("I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON (de)serialization failed: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, PuzzleError>;
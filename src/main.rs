@@ -1,18 +1,31 @@
 // Solve the following puzzle:
-// You have 12 masses and a balance scale. 11 of the masses are the same weight, but one is different.
+// You have 12 masses and a balance scale. 11 of the masses are the same weight, but one is
+// different - it could be either heavier or lighter, and you don't know which.
 // You can only use the balance scale 3 times. How do you find the different weight?
 //
 // This tool lets you try out a method to solve the puzzle.
 // It won't tell you whether you have found the consistent method though.
 
-use core::panic;
+use clap::{Parser, Subcommand};
+use colored::Color;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
-use rand::Rng;
+mod bench;
+mod color;
+mod error;
+mod repl;
+mod strategy;
+mod transcript;
 
-#[derive(PartialEq, Clone, Debug)]
+use error::{PuzzleError, Result};
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 enum MassWeight {
-    Different,
     Same,
+    Heavier,
+    Lighter,
 }
 
 #[derive(Clone, Debug)]
@@ -21,33 +34,61 @@ struct Mass {
     weight: MassWeight,
 }
 
-#[derive(PartialEq, Debug)]
+impl std::fmt::Display for Mass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.weight {
+            MassWeight::Same => write!(f, "{}", self.name),
+            MassWeight::Heavier => write!(f, "{}", color::style(&self.name.to_string(), Color::Red)),
+            MassWeight::Lighter => {
+                write!(f, "{}", color::style(&self.name.to_string(), Color::Cyan))
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 enum Balance {
     Balanced,
     LeftHeavy,
     RightHeavy,
 }
 
-fn get_input() -> String {
+impl std::fmt::Display for Balance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Balance::Balanced => write!(f, "{}", color::dim("Balanced")),
+            Balance::LeftHeavy => write!(f, "{}", color::style("Left Heavy", Color::Green)),
+            Balance::RightHeavy => write!(f, "{}", color::style("Right Heavy", Color::Green)),
+        }
+    }
+}
+
+/// Read one line from stdin, or `Ok(None)` if stdin has hit EOF (a
+/// 0-byte read from `read_line`), so callers can quit instead of looping
+/// forever re-reading an empty line.
+fn get_input() -> Result<Option<String>> {
     let mut input = String::new();
-    input.clear();
-    std::io::stdin().read_line(&mut input).unwrap();
-    input
+    let bytes_read = std::io::stdin().read_line(&mut input)?;
+    if bytes_read == 0 {
+        return Ok(None);
+    }
+    Ok(Some(input))
 }
 
-fn select_masses(masses: &Vec<Mass>, selection: String) -> Vec<Mass> {
+fn select_masses(masses: &[Mass], selection: String) -> Result<Vec<Mass>> {
     let mut masses_out = Vec::new();
     for c in selection.to_uppercase().chars() {
         if c.is_alphabetic() {
-            if let Some(mass) = masses.iter().find(|m| m.name == c) {
-                masses_out.push(mass.clone());
+            match masses.iter().find(|m| m.name == c) {
+                Some(mass) => masses_out.push(mass.clone()),
+                None => return Err(PuzzleError::UnknownMass(c)),
             }
         }
     }
-    masses_out
+    Ok(masses_out)
 }
 
-fn get_mass_names(masses: &Vec<Mass>) -> String {
+fn get_mass_names(masses: &[Mass]) -> String {
     let mut names = String::new();
     for mass in masses {
         names.push(mass.name);
@@ -55,21 +96,17 @@ fn get_mass_names(masses: &Vec<Mass>) -> String {
     names
 }
 
-fn weigh(left: &Vec<Mass>, right: &Vec<Mass>) -> Balance {
-    let left_weight: i32 = left
-        .iter()
-        .map(|m| match m.weight {
-            MassWeight::Different => 1,
-            MassWeight::Same => 0,
-        })
-        .sum();
-    let right_weight: i32 = right
-        .iter()
-        .map(|m| match m.weight {
-            MassWeight::Different => 1,
-            MassWeight::Same => 0,
-        })
-        .sum();
+fn signed_weight(mass: &Mass) -> i32 {
+    match mass.weight {
+        MassWeight::Heavier => 1,
+        MassWeight::Lighter => -1,
+        MassWeight::Same => 0,
+    }
+}
+
+fn weigh(left: &[Mass], right: &[Mass]) -> Balance {
+    let left_weight: i32 = left.iter().map(signed_weight).sum();
+    let right_weight: i32 = right.iter().map(signed_weight).sum();
     if left_weight > right_weight {
         Balance::LeftHeavy
     } else if left_weight < right_weight {
@@ -79,228 +116,295 @@ fn weigh(left: &Vec<Mass>, right: &Vec<Mass>) -> Balance {
     }
 }
 
-fn get_answer(masses: &Vec<Mass>) -> String {
+fn get_answer(masses: &[Mass]) -> String {
     let mut answer = String::new();
     for mass in masses {
-        if mass.weight == MassWeight::Different {
+        if mass.weight != MassWeight::Same {
             answer.push(mass.name);
         }
     }
     answer
 }
 
-fn guess(masses: &Vec<Mass>) -> bool {
-    let mut input = String::new();
-    input.clear();
-    std::io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_uppercase() == get_answer(masses)
+fn get_answer_direction(masses: &[Mass]) -> Option<MassWeight> {
+    masses
+        .iter()
+        .map(|m| m.weight)
+        .find(|weight| *weight != MassWeight::Same)
+}
+
+/// Like `get_answer`, but with the culprit mass styled via its `Display`
+/// impl - for printing at the reveal, never during active play.
+fn reveal_answer(masses: &[Mass]) -> String {
+    masses
+        .iter()
+        .filter(|m| m.weight != MassWeight::Same)
+        .map(|m| m.to_string())
+        .collect()
 }
 
-fn setup_masses() -> Vec<Mass> {
+/// Build `n_masses` masses (named `A`, `B`, ... - so `n_masses` must be at
+/// most 26), with one randomly chosen to be the odd one out, heavier or
+/// lighter with equal probability.
+fn setup_masses(n_masses: usize, rng: &mut impl Rng) -> Vec<Mass> {
     let mut masses = Vec::new();
-    for c in 'A'..='L' {
+    for i in 0..n_masses {
         masses.push(Mass {
-            name: c,
+            name: (b'A' + i as u8) as char,
             weight: MassWeight::Same,
         });
     }
-    let index_of_different = rand::thread_rng().gen_range(0..12);
-    masses[index_of_different as usize].weight = MassWeight::Different;
+    let index_of_different = rng.gen_range(0..n_masses);
+    let direction = if rng.gen_bool(0.5) {
+        MassWeight::Heavier
+    } else {
+        MassWeight::Lighter
+    };
+    masses[index_of_different].weight = direction;
     masses
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 enum GameResult {
     Win,
     Lose,
 }
 
-fn manual_solve() -> GameResult {
-    let masses = setup_masses();
-    let mut measurements = 3;
-
-    while measurements > 0 {
-        println!("\n-------------------\n");
-        println!("Measurements left: {}", measurements);
-
-        println!("Which masses would you like to put on the left side of the scale?");
-        let left_selection = get_input();
-        let left_side = select_masses(&masses, left_selection);
-        println!("Left side: {:?}", get_mass_names(&left_side));
-
-        println!("Which masses would you like to put on the right side of the scale?");
-        let right_selection = get_input();
-        let right_side = select_masses(&masses, right_selection);
-        println!("Right side: {:?}", get_mass_names(&right_side));
-
-        let balance = weigh(&left_side, &right_side);
-
-        println!("The balance is: {:?}", balance);
-        measurements -= 1;
+fn ez_measure(masses: &[Mass], left: String, right: String, verbose: bool) -> Result<Balance> {
+    let left_side = select_masses(masses, left)?;
+    let right_side = select_masses(masses, right)?;
+    if left_side.is_empty() || right_side.is_empty() {
+        return Err(PuzzleError::EmptyPan);
     }
-
-    println!("\n-------------------\n");
-
-    println!("You have no more measurements left.");
-    println!("What do you think the different mass is?");
-
-    if guess(&masses) {
-        println!("You found the different mass!");
-        println!("The different mass was: {}", get_answer(&masses));
-        return GameResult::Win;
-    } else {
-        println!("You didn't find the different mass.");
-        println!("The different mass was: {}", get_answer(&masses));
-        return GameResult::Lose;
+    if left_side.len() != right_side.len() {
+        return Err(PuzzleError::UnequalPans {
+            left: left_side.len(),
+            right: right_side.len(),
+        });
     }
-}
-
-fn ez_measure(masses: &Vec<Mass>, left: String, right: String, verbose: bool) -> Balance {
-    let left_side = select_masses(&masses, left);
-    let right_side = select_masses(&masses, right);
     let balance = weigh(&left_side, &right_side);
 
     if verbose {
-        println!("Left side: {:?}", get_mass_names(&left_side));
-        println!("Right side: {:?}", get_mass_names(&right_side));
-        println!("The balance is: {:?}", balance);
+        println!("Left side: {}", get_mass_names(&left_side));
+        println!("Right side: {}", get_mass_names(&right_side));
+        println!("The balance is: {}", balance);
         println!("\n-------------------\n");
     }
-    balance
+    Ok(balance)
 }
 
-enum ResultComparison {
-    Same,
-    Opposite,
-    Balanced,
+fn pan_to_string(indices: &[usize]) -> String {
+    indices.iter().map(|i| (b'A' + *i as u8) as char).collect()
 }
 
-fn compare_results(first: &Balance, second: &Balance) -> ResultComparison {
-    if *second == Balance::Balanced {
-        return ResultComparison::Balanced;
-    } else if first == second {
-        return ResultComparison::Same;
-    } else {
-        return ResultComparison::Opposite;
+fn walk_strategy(
+    tree: &strategy::StrategyTree,
+    masses: &[Mass],
+    verbose: bool,
+    weighings: &mut Vec<transcript::Weighing>,
+) -> Result<char> {
+    match tree {
+        strategy::StrategyTree::Leaf { mass_index, .. } => Ok(masses[*mass_index].name),
+        strategy::StrategyTree::Weigh {
+            pans,
+            balanced,
+            left_heavy,
+            right_heavy,
+        } => {
+            let left = pan_to_string(&pans.left);
+            let right = pan_to_string(&pans.right);
+            let balance = ez_measure(masses, left.clone(), right.clone(), verbose)?;
+            weighings.push(transcript::Weighing {
+                left,
+                right,
+                balance,
+            });
+            match balance {
+                Balance::Balanced => walk_strategy(balanced, masses, verbose, weighings),
+                Balance::LeftHeavy => walk_strategy(left_heavy, masses, verbose, weighings),
+                Balance::RightHeavy => walk_strategy(right_heavy, masses, verbose, weighings),
+            }
+        }
     }
 }
 
-fn auto_solve(verbose: bool) -> GameResult {
-    let masses = setup_masses();
-
+/// Run one auto-solve against an already-generated `tree` and return both
+/// its win/lose verdict and a transcript of every weighing along the way,
+/// suitable for saving to disk or feeding into a regression test.
+///
+/// `tree` is generated once by the caller and reused across attempts -
+/// generating it from scratch is by far the most expensive part of a
+/// solve, so `auto`/`bench` only pay for it once per invocation.
+fn auto_solve(
+    tree: &strategy::StrategyTree,
+    verbose: bool,
+    n_masses: usize,
+    n_weighings: usize,
+    rng: &mut impl Rng,
+) -> Result<(GameResult, transcript::Transcript)> {
+    let masses = setup_masses(n_masses, rng);
     let answer = get_answer(&masses);
-    let final_result: char;
-
-    let result_1 = ez_measure(&masses, "ABCD".to_string(), "EFGH".to_string(), verbose);
-
-    match result_1 {
-        Balance::Balanced => {
-            let result_2 = ez_measure(&masses, "IJ".to_string(), "KA".to_string(), verbose);
-            match result_2 {
-                Balance::Balanced => final_result = 'L',
-                _ => {
-                    let result_3 = ez_measure(&masses, "JK".to_string(), "AB".to_string(), verbose);
-                    let comparison = compare_results(&result_2, &result_3);
-                    match comparison {
-                        ResultComparison::Balanced => final_result = 'I',
-                        ResultComparison::Same => final_result = 'J',
-                        ResultComparison::Opposite => final_result = 'K',
-                    }
-                }
-            }
-        }
-        _ => {
-            let result_2 = ez_measure(&masses, "ABE".to_string(), "CDF".to_string(), verbose);
-            match result_2 {
-                Balance::Balanced => {
-                    let result_3 = ez_measure(&masses, "G".to_string(), "I".to_string(), verbose);
-                    match result_3 {
-                        Balance::Balanced => final_result = 'H',
-                        _ => final_result = 'G',
-                    }
-                }
-                _ => {
-                    let result_3 = ez_measure(&masses, "ED".to_string(), "FB".to_string(), verbose);
-                    let comparisons = (
-                        compare_results(&result_1, &result_2),
-                        compare_results(&result_2, &result_3),
-                    );
-                    match comparisons {
-                        (ResultComparison::Same, ResultComparison::Balanced) => final_result = 'A',
-                        (ResultComparison::Same, ResultComparison::Same) => final_result = 'F',
-                        (ResultComparison::Same, ResultComparison::Opposite) => final_result = 'B',
-                        (ResultComparison::Opposite, ResultComparison::Balanced) => {
-                            final_result = 'C'
-                        }
-                        (ResultComparison::Opposite, ResultComparison::Same) => final_result = 'E',
-                        (ResultComparison::Opposite, ResultComparison::Opposite) => {
-                            final_result = 'D'
-                        }
-                        _ => panic!("This should never happen!"),
-                    }
-                }
-            }
-        }
-    }
-    println!("Auto-solve result: {}", final_result);
-    println!("The different mass was: {}", answer);
+    let answer_char = answer.chars().next().unwrap();
+
+    let mut weighings = Vec::new();
+    let guess = walk_strategy(tree, &masses, verbose, &mut weighings)?;
 
-    if final_result == answer.chars().next().unwrap() {
-        return GameResult::Win;
+    println!("Auto-solve result: {}", guess);
+    println!("The different mass was: {}", reveal_answer(&masses));
+
+    let result = if guess == answer_char {
+        GameResult::Win
     } else {
-        return GameResult::Lose;
-    }
+        GameResult::Lose
+    };
+    let record = transcript::Transcript {
+        n_masses,
+        n_weighings,
+        weighings,
+        guess,
+        answer: answer_char,
+        result,
+    };
+    Ok((result, record))
 }
 
-enum SolveMethod {
-    Manual,
-    Auto,
+/// Solve the N-masses/R-weighings balance puzzle.
+#[derive(Parser)]
+#[command(name = "techno-weights")]
+struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+
+    /// Number of masses, one of which is the odd one out (at most 26).
+    #[arg(long, global = true, default_value_t = 12)]
+    masses: usize,
+
+    /// Number of weighings available to find it.
+    #[arg(long, global = true, default_value_t = 3)]
+    weighings: usize,
+
+    /// Color output: auto, always, or never.
+    #[arg(long, global = true, default_value = "auto")]
+    color: String,
 }
 
-fn solve(method: SolveMethod, verbose: bool) -> GameResult {
-    match method {
-        SolveMethod::Manual => manual_solve(),
-        SolveMethod::Auto => auto_solve(verbose),
-    }
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Solve the puzzle interactively via the REPL.
+    Play,
+    /// Run the generated solver automatically.
+    Auto {
+        /// How many times to run the solver.
+        #[arg(long, default_value_t = 1)]
+        attempts: u32,
+        /// Print each weighing as it happens.
+        #[arg(long)]
+        verbose: bool,
+        /// Seed the RNG, for reproducing a specific run.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Save every run's transcript as a JSON array to this file.
+        #[arg(long)]
+        transcript_out: Option<std::path::PathBuf>,
+    },
+    /// Run many auto-solves in parallel and report aggregate stats.
+    Bench {
+        /// How many auto-solves to run.
+        #[arg(long, default_value_t = 1000)]
+        attempts: usize,
+    },
+    /// Generate a strategy and print or save it as JSON, or reload one
+    /// previously saved to check it's still valid.
+    Strategy {
+        /// Write the strategy here instead of printing it to stdout.
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+        /// Reload a previously saved strategy instead of generating one.
+        #[arg(long)]
+        load: Option<std::path::PathBuf>,
+    },
 }
 
-fn main() {
-    let mut startover = true;
-    while startover {
-        print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
-        println!("12 Masses Puzzle");
-        println!("-------------------\n");
-        println!("Would you like to solve the puzzle manually or automatically?");
-        println!("Type 'manual' or 'auto' and press Enter. (m or a works)");
-        let method = get_input().trim().to_lowercase();
-        if method.starts_with('m') {
-            solve(SolveMethod::Manual, true);
-        } else if method.starts_with('a') {
-            let mut record: Vec<GameResult> = Vec::new();
-
-            println!("How many times should the computer solve the puzzle?");
-            let attempts = get_input().trim().parse::<i32>().unwrap();
-
-            println!("Would you like to see the steps? (y/n)");
-            let verbose = get_input().trim().to_lowercase();
-            if verbose.starts_with('y') {
-                for _ in 0..attempts {
-                    record.push(solve(SolveMethod::Auto, true));
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    if cli.masses == 0 {
+        return Err(PuzzleError::TooFewMasses);
+    }
+    if cli.masses > 26 {
+        return Err(PuzzleError::TooManyMasses(cli.masses));
+    }
+
+    color::set_mode(match cli.color.as_str() {
+        "always" => color::ColorMode::Always,
+        "never" => color::ColorMode::Never,
+        _ => color::ColorMode::Auto,
+    });
+
+    match cli.command {
+        CliCommand::Play => repl::run(cli.masses, cli.weighings),
+        CliCommand::Auto {
+            attempts,
+            verbose,
+            seed,
+            transcript_out,
+        } => {
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let tree = strategy::StrategyTree::generate(cli.masses, cli.weighings)?;
+            let mut results: Vec<GameResult> = Vec::new();
+            let mut transcripts: Vec<transcript::Transcript> = Vec::new();
+            for _ in 0..attempts {
+                let (result, record) =
+                    auto_solve(&tree, verbose, cli.masses, cli.weighings, &mut rng)?;
+                results.push(result);
+                transcripts.push(record);
+            }
+            println!("Results: {:?}", results);
+            if let Some(path) = transcript_out {
+                std::fs::write(&path, serde_json::to_string_pretty(&transcripts)?)?;
+                println!(
+                    "Wrote {} transcript(s) to {}",
+                    transcripts.len(),
+                    path.display()
+                );
+            }
+        }
+        CliCommand::Bench { attempts } => {
+            let report = bench::run(attempts, cli.masses, cli.weighings)?;
+            println!("Runs: {}", report.runs);
+            println!(
+                "Wins: {}  Losses: {}  Win rate: {:.2}%",
+                report.wins,
+                report.losses,
+                report.win_rate() * 100.0
+            );
+            println!(
+                "Per-solve time - min: {:?}  mean: {:?}  max: {:?}",
+                report.min_time, report.mean_time, report.max_time
+            );
+            println!("Throughput: {:.0} solves/sec", report.throughput());
+        }
+        CliCommand::Strategy { out, load } => {
+            let tree = match load {
+                Some(path) => {
+                    let json = std::fs::read_to_string(&path)?;
+                    strategy::StrategyTree::from_json(&json)?
                 }
-            } else {
-                for _ in 0..attempts {
-                    record.push(solve(SolveMethod::Auto, false));
+                None => strategy::StrategyTree::generate(cli.masses, cli.weighings)?,
+            };
+            let json = tree.to_json()?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, &json)?;
+                    println!("Wrote strategy to {}", path.display());
                 }
+                None => println!("{}", json),
             }
-            println!("Results: {:?}", record);
-        } else {
-            println!("Invalid input.");
-        }
-
-        println!("\n\nWould you like to start over? (y/anything else)");
-        let startover_input = get_input().trim().to_lowercase();
-        if !startover_input.starts_with('y') {
-            startover = false;
         }
     }
+
+    Ok(())
 }
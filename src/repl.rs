@@ -0,0 +1,207 @@
+// Interactive REPL for manual solving: named commands instead of the old
+// "pick your left side, now your right side" positional prompts.
+//
+// Between weighings we keep a live set of hypotheses (which mass is odd,
+// and whether it's heavier or lighter) consistent with every `Balance`
+// seen so far. That set is what powers `state` and `hint`, and lets us
+// notice when the player has already solved the puzzle logically, even
+// before their measurements run out.
+
+use std::io::Write;
+
+use crate::error::Result;
+use crate::strategy::{self, Direction, Hypothesis, StrategyTree};
+use crate::{ez_measure, get_answer, get_answer_direction, get_input, reveal_answer, setup_masses, Mass};
+
+enum Command {
+    Weigh(String, String),
+    State,
+    Guess(String),
+    Hint,
+    Restart,
+    Quit,
+    Unknown(String),
+}
+
+fn parse(line: &str) -> Command {
+    let mut parts = line.split_whitespace();
+    match parts.next().map(|s| s.to_lowercase()).as_deref() {
+        Some("weigh") => Command::Weigh(
+            parts.next().unwrap_or_default().to_string(),
+            parts.next().unwrap_or_default().to_string(),
+        ),
+        Some("state") => Command::State,
+        Some("guess") => Command::Guess(parts.collect::<Vec<_>>().join(" ")),
+        Some("hint") => Command::Hint,
+        Some("restart") => Command::Restart,
+        Some("quit") | Some("exit") => Command::Quit,
+        _ => Command::Unknown(line.trim().to_string()),
+    }
+}
+
+fn mass_index(masses: &[Mass], name: char) -> Option<usize> {
+    masses.iter().position(|m| m.name == name)
+}
+
+fn letters_to_indices(masses: &[Mass], letters: &str) -> Vec<usize> {
+    letters
+        .to_uppercase()
+        .chars()
+        .filter_map(|c| mass_index(masses, c))
+        .collect()
+}
+
+fn indices_to_letters(masses: &[Mass], indices: &[usize]) -> String {
+    indices.iter().map(|&i| masses[i].name).collect()
+}
+
+/// A session's live set of still-possible hypotheses, one distinct mass
+/// index per remaining candidate.
+fn remaining_candidates(hypotheses: &[Hypothesis]) -> Vec<usize> {
+    let mut indices: Vec<usize> = hypotheses.iter().map(|h| h.mass_index).collect();
+    indices.sort();
+    indices.dedup();
+    indices
+}
+
+struct Session {
+    masses: Vec<Mass>,
+    n_masses: usize,
+    n_weighings: usize,
+    measurements_left: usize,
+    hypotheses: Vec<Hypothesis>,
+}
+
+impl Session {
+    fn new(n_masses: usize, n_weighings: usize) -> Self {
+        let masses = setup_masses(n_masses, &mut rand::thread_rng());
+        let hypotheses = strategy::all_hypotheses(n_masses);
+        Session {
+            measurements_left: n_weighings,
+            hypotheses,
+            masses,
+            n_masses,
+            n_weighings,
+        }
+    }
+
+    fn solved(&self) -> bool {
+        remaining_candidates(&self.hypotheses).len() == 1
+    }
+
+    fn print_state(&self) {
+        let candidates = remaining_candidates(&self.hypotheses);
+        println!("Measurements left: {}", self.measurements_left);
+        println!(
+            "Remaining candidates: {}",
+            candidates
+                .iter()
+                .map(|&i| self.masses[i].name)
+                .collect::<String>()
+        );
+        if self.solved() {
+            println!("You have enough information to guess the culprit mass.");
+        }
+    }
+
+    fn weigh(&mut self, left: &str, right: &str) -> Result<()> {
+        if self.measurements_left == 0 {
+            println!("No measurements left. Use `guess` or `restart`.");
+            return Ok(());
+        }
+
+        let balance = ez_measure(&self.masses, left.to_string(), right.to_string(), true)?;
+        let left_indices = letters_to_indices(&self.masses, left);
+        let right_indices = letters_to_indices(&self.masses, right);
+        self.hypotheses =
+            strategy::filter_consistent(&self.hypotheses, &left_indices, &right_indices, balance);
+        self.measurements_left -= 1;
+        Ok(())
+    }
+
+    fn hint(&self) {
+        if self.measurements_left == 0 {
+            println!("No measurements left - time to guess.");
+            return;
+        }
+        match StrategyTree::generate_for(&self.hypotheses, self.masses.len(), self.measurements_left) {
+            Ok(StrategyTree::Weigh { pans, .. }) => println!(
+                "Try weighing {} against {}.",
+                indices_to_letters(&self.masses, &pans.left),
+                indices_to_letters(&self.masses, &pans.right)
+            ),
+            Ok(StrategyTree::Leaf { mass_index, direction }) => {
+                let direction = match direction {
+                    Some(Direction::Heavy) => " (heavier)",
+                    Some(Direction::Light) => " (lighter)",
+                    None => "",
+                };
+                println!("It's {}{}.", self.masses[mass_index].name, direction);
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    fn guess(&self, answer: &str) -> bool {
+        let answer = answer.trim().to_uppercase();
+        let mut parts = answer.split_whitespace();
+
+        if parts.next() != Some(get_answer(&self.masses).as_str()) {
+            return false;
+        }
+        match parts.next() {
+            Some("H") => get_answer_direction(&self.masses) == Some(crate::MassWeight::Heavier),
+            Some("L") => get_answer_direction(&self.masses) == Some(crate::MassWeight::Lighter),
+            Some(_) => false,
+            None => true,
+        }
+    }
+}
+
+pub fn run(n_masses: usize, n_weighings: usize) {
+    println!("Manual REPL - commands: weigh <LEFT> <RIGHT>, state, hint, guess <LETTER> [H|L], restart, quit");
+    let mut session = Session::new(n_masses, n_weighings);
+
+    loop {
+        print!("> ");
+        if let Err(e) = std::io::stdout().flush() {
+            println!("{}", e);
+            continue;
+        }
+        let line = match get_input() {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                println!("Couldn't read that line ({}) - try again.", e);
+                continue;
+            }
+        };
+        match parse(&line) {
+            Command::Weigh(left, right) => {
+                if let Err(e) = session.weigh(&left, &right) {
+                    println!("{}", e);
+                }
+            }
+            Command::State => session.print_state(),
+            Command::Hint => session.hint(),
+            Command::Guess(answer) => {
+                if session.guess(&answer) {
+                    println!("Correct! The different mass was: {}", reveal_answer(&session.masses));
+                } else {
+                    println!(
+                        "Not quite. The different mass was: {}",
+                        reveal_answer(&session.masses)
+                    );
+                }
+                println!("Restarting...");
+                session = Session::new(session.n_masses, session.n_weighings);
+            }
+            Command::Restart => {
+                println!("Restarting...");
+                session = Session::new(session.n_masses, session.n_weighings);
+            }
+            Command::Quit => break,
+            Command::Unknown(line) => println!("Unrecognized command: `{}`", line),
+        }
+    }
+}
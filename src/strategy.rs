@@ -0,0 +1,341 @@
+// Generalized information-theoretic strategy generation for the N-mass,
+// R-weighing balance puzzle.
+//
+// Rather than hand-coding a decision tree for exactly 12 masses and 3
+// weighings, this module searches for an optimal weighing strategy for
+// any `(n_masses, n_weighings)` pair and exposes it as a `StrategyTree`
+// that `auto_solve` can walk.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PuzzleError, Result};
+use crate::Balance;
+
+/// Which way a suspected odd mass might be throwing off the balance.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Direction {
+    Heavy,
+    Light,
+}
+
+/// A candidate explanation for the observed (im)balance so far: "mass
+/// `mass_index` is the odd one out, and it's `direction`".
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Hypothesis {
+    pub mass_index: usize,
+    pub direction: Direction,
+}
+
+/// A weighing of two disjoint, equal-size sets of mass indices.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Pans {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+/// A node in a generated strategy: either a weighing to perform next,
+/// with a branch per possible outcome, or a leaf naming the culprit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum StrategyTree {
+    Weigh {
+        pans: Pans,
+        balanced: Box<StrategyTree>,
+        left_heavy: Box<StrategyTree>,
+        right_heavy: Box<StrategyTree>,
+    },
+    Leaf {
+        mass_index: usize,
+        direction: Option<Direction>,
+    },
+}
+
+type Key = (Vec<(usize, Direction)>, usize);
+
+impl StrategyTree {
+    /// Compute an optimal strategy for distinguishing the odd mass among
+    /// `n_masses` candidates in at most `n_weighings` weighings, assuming
+    /// the direction (heavy or light) is unknown up front.
+    ///
+    /// Returns `Err(PuzzleError::NoStrategy { .. })` if the search finds no
+    /// strategy within this many weighings. That is always the case when
+    /// `2 * n_masses > 3^n_weighings`, but the bound alone is not enough:
+    /// 13 masses in 3 weighings also has no strategy.
+    pub fn generate(n_masses: usize, n_weighings: usize) -> Result<StrategyTree> {
+        Self::generate_for(&all_hypotheses(n_masses), n_masses, n_weighings)
+    }
+
+    /// Like [`generate`](Self::generate), but starting from a
+    /// caller-supplied hypothesis set instead of "all `n_masses`
+    /// candidates, direction unknown". Used to pick up mid-game, once
+    /// some weighings have already narrowed things down.
+    pub fn generate_for(
+        hypotheses: &[Hypothesis],
+        n_masses: usize,
+        n_weighings: usize,
+    ) -> Result<StrategyTree> {
+        let mut memo = HashMap::new();
+        solve(hypotheses, n_masses, n_weighings, &mut memo).ok_or(PuzzleError::NoStrategy {
+            n_masses,
+            n_weighings,
+        })
+    }
+
+    /// Serialize this strategy to pretty-printed JSON, e.g. to save a
+    /// known-good strategy to disk or diff it across versions.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reload a strategy previously saved with [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<StrategyTree> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// All `2 * n_masses` hypotheses for `n_masses` candidates with unknown
+/// direction.
+pub fn all_hypotheses(n_masses: usize) -> Vec<Hypothesis> {
+    (0..n_masses)
+        .flat_map(|mass_index| {
+            [Direction::Heavy, Direction::Light]
+                .into_iter()
+                .map(move |direction| Hypothesis {
+                    mass_index,
+                    direction,
+                })
+        })
+        .collect()
+}
+
+/// Narrow `hypotheses` down to those consistent with having observed
+/// `outcome` when weighing `left` against `right`.
+pub fn filter_consistent(
+    hypotheses: &[Hypothesis],
+    left: &[usize],
+    right: &[usize],
+    outcome: Balance,
+) -> Vec<Hypothesis> {
+    hypotheses
+        .iter()
+        .copied()
+        .filter(|h| predict(*h, left, right) == outcome)
+        .collect()
+}
+
+/// A hypothesis set is solvable in `weighings` weighings only if its size
+/// is at most `3^weighings` (each weighing splits the set into at most 3
+/// parts). Sets within the bound can still be unsolvable.
+fn max_distinguishable(weighings: usize) -> usize {
+    3usize.pow(weighings as u32)
+}
+
+fn canonical_key(hypotheses: &[Hypothesis], n_weighings: usize) -> Key {
+    let mut key: Vec<(usize, Direction)> = hypotheses
+        .iter()
+        .map(|h| (h.mass_index, h.direction))
+        .collect();
+    key.sort();
+    (key, n_weighings)
+}
+
+fn solve(
+    hypotheses: &[Hypothesis],
+    n_masses: usize,
+    n_weighings: usize,
+    memo: &mut HashMap<Key, Option<StrategyTree>>,
+) -> Option<StrategyTree> {
+    if hypotheses.is_empty() {
+        // A pigeonhole split of few hypotheses across 3 outcomes routinely
+        // leaves one outcome with no consistent hypothesis at all - it just
+        // can't happen given the real masses. There's nothing to resolve,
+        // so this branch of the tree is simply never walked in practice.
+        return Some(StrategyTree::Leaf {
+            mass_index: 0,
+            direction: None,
+        });
+    }
+    if hypotheses.len() == 1 {
+        let h = hypotheses[0];
+        return Some(StrategyTree::Leaf {
+            mass_index: h.mass_index,
+            direction: Some(h.direction),
+        });
+    }
+    if n_weighings == 0 || hypotheses.len() > max_distinguishable(n_weighings) {
+        return None;
+    }
+
+    let key = canonical_key(hypotheses, n_weighings);
+    if let Some(cached) = memo.get(&key) {
+        return cached.clone();
+    }
+
+    let mut active: Vec<usize> = hypotheses.iter().map(|h| h.mass_index).collect();
+    active.sort();
+    active.dedup();
+    // Masses already known to be genuine can still be used as reference
+    // filler on either pan.
+    let known_good: Vec<usize> = (0..n_masses).filter(|i| !active.contains(i)).collect();
+    let pool: Vec<usize> = active.iter().chain(known_good.iter()).copied().collect();
+
+    let target = max_distinguishable(n_weighings - 1);
+    let mut best: Option<(StrategyTree, usize)> = None;
+
+    'search: for pan_size in 1..=n_masses / 2 {
+        for left in combinations(&pool, pan_size) {
+            let remaining: Vec<usize> = pool
+                .iter()
+                .copied()
+                .filter(|i| !left.contains(i))
+                .collect();
+            for right in combinations(&remaining, pan_size) {
+                let (balanced, left_heavy, right_heavy) = partition(hypotheses, &left, &right);
+                if balanced.len() == hypotheses.len() {
+                    // This weighing can't possibly be informative.
+                    continue;
+                }
+
+                let worst = balanced.len().max(left_heavy.len()).max(right_heavy.len());
+                if best.as_ref().is_some_and(|(_, w)| worst >= *w) {
+                    continue;
+                }
+
+                let branches = (
+                    solve(&balanced, n_masses, n_weighings - 1, memo),
+                    solve(&left_heavy, n_masses, n_weighings - 1, memo),
+                    solve(&right_heavy, n_masses, n_weighings - 1, memo),
+                );
+                if let (Some(b), Some(lh), Some(rh)) = branches {
+                    let candidate = StrategyTree::Weigh {
+                        pans: Pans {
+                            left: left.clone(),
+                            right: right.clone(),
+                        },
+                        balanced: Box::new(b),
+                        left_heavy: Box::new(lh),
+                        right_heavy: Box::new(rh),
+                    };
+                    best = Some((candidate, worst));
+                    if worst <= target {
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+
+    let result = best.map(|(tree, _)| tree);
+    memo.insert(key, result.clone());
+    result
+}
+
+/// Split `hypotheses` into the three outcomes a weighing of `left`
+/// against `right` would produce.
+fn partition(
+    hypotheses: &[Hypothesis],
+    left: &[usize],
+    right: &[usize],
+) -> (Vec<Hypothesis>, Vec<Hypothesis>, Vec<Hypothesis>) {
+    let mut balanced = Vec::new();
+    let mut left_heavy = Vec::new();
+    let mut right_heavy = Vec::new();
+
+    for &h in hypotheses {
+        match predict(h, left, right) {
+            Balance::Balanced => balanced.push(h),
+            Balance::LeftHeavy => left_heavy.push(h),
+            Balance::RightHeavy => right_heavy.push(h),
+        }
+    }
+    (balanced, left_heavy, right_heavy)
+}
+
+/// What a single hypothesis predicts a weighing of `left` against
+/// `right` would show.
+fn predict(hypothesis: Hypothesis, left: &[usize], right: &[usize]) -> Balance {
+    let on_left = left.contains(&hypothesis.mass_index);
+    let on_right = right.contains(&hypothesis.mass_index);
+    match (on_left, on_right, hypothesis.direction) {
+        (true, false, Direction::Heavy) => Balance::LeftHeavy,
+        (true, false, Direction::Light) => Balance::RightHeavy,
+        (false, true, Direction::Heavy) => Balance::RightHeavy,
+        (false, true, Direction::Light) => Balance::LeftHeavy,
+        _ => Balance::Balanced,
+    }
+}
+
+/// All `k`-element subsets of `items`, preserving relative order.
+fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if items.len() < k {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    for i in 0..=items.len() - k {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, items[i]);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walk `tree` as if `truth` were the real odd mass, and return the
+    /// hypothesis it lands on.
+    fn walk(tree: &StrategyTree, truth: Hypothesis) -> Hypothesis {
+        match tree {
+            StrategyTree::Leaf {
+                mass_index,
+                direction,
+            } => Hypothesis {
+                mass_index: *mass_index,
+                direction: direction.unwrap_or(truth.direction),
+            },
+            StrategyTree::Weigh {
+                pans,
+                balanced,
+                left_heavy,
+                right_heavy,
+            } => {
+                let branch = match predict(truth, &pans.left, &pans.right) {
+                    Balance::Balanced => balanced,
+                    Balance::LeftHeavy => left_heavy,
+                    Balance::RightHeavy => right_heavy,
+                };
+                walk(branch, truth)
+            }
+        }
+    }
+
+    /// Generate a strategy for `(n_masses, n_weighings)` and check it
+    /// correctly identifies every possible odd mass, not just that
+    /// `generate` returned `Ok`.
+    fn assert_distinguishes(n_masses: usize, n_weighings: usize) {
+        let tree = StrategyTree::generate(n_masses, n_weighings).unwrap_or_else(|e| {
+            panic!("{n_masses} masses / {n_weighings} weighings should be solvable: {e}")
+        });
+        for truth in all_hypotheses(n_masses) {
+            assert_eq!(walk(&tree, truth), truth);
+        }
+    }
+
+    #[test]
+    fn solves_the_headline_twelve_masses_three_weighings_case() {
+        assert_distinguishes(12, 3);
+    }
+
+    #[test]
+    fn solves_a_few_other_solvable_cases() {
+        assert_distinguishes(3, 2);
+        assert_distinguishes(4, 3);
+    }
+}
@@ -0,0 +1,87 @@
+// Recorded transcripts of a completed auto-solve: every weighing made,
+// the balance it showed, and the final guess versus the true answer.
+//
+// Transcripts serialize to JSON so a regression test can replay one and
+// assert the solver still reaches the same culprit, without re-running
+// the randomized setup that produced it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Balance, GameResult};
+
+/// One weighing performed while walking a strategy: which masses went on
+/// each pan, and how the balance tipped.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Weighing {
+    pub left: String,
+    pub right: String,
+    pub balance: Balance,
+}
+
+/// A full record of one auto-solve: its weighings in order, the solver's
+/// final guess, the true answer, and whether they matched.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transcript {
+    pub n_masses: usize,
+    pub n_weighings: usize,
+    pub weighings: Vec<Weighing>,
+    pub guess: char,
+    pub answer: char,
+    pub result: GameResult,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::strategy;
+
+    /// Actually run the solver (not just round-trip a hand-built
+    /// `Transcript`) and check it reaches the correct culprit, so this
+    /// module also covers what transcripts are meant to regression-test.
+    #[test]
+    fn seeded_auto_solve_reaches_the_correct_culprit() {
+        let tree = strategy::StrategyTree::generate(12, 3).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let (result, transcript) = crate::auto_solve(&tree, false, 12, 3, &mut rng).unwrap();
+
+        assert_eq!(result, GameResult::Win);
+        assert_eq!(transcript.guess, transcript.answer);
+        assert_eq!(transcript.weighings.len(), 3);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let original = Transcript {
+            n_masses: 12,
+            n_weighings: 3,
+            weighings: vec![
+                Weighing {
+                    left: "ABCD".to_string(),
+                    right: "EFGH".to_string(),
+                    balance: Balance::Balanced,
+                },
+                Weighing {
+                    left: "A".to_string(),
+                    right: "I".to_string(),
+                    balance: Balance::LeftHeavy,
+                },
+            ],
+            guess: 'A',
+            answer: 'A',
+            result: GameResult::Win,
+        };
+
+        let json = serde_json::to_string(&original).unwrap();
+        let reloaded: Transcript = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(reloaded.n_masses, original.n_masses);
+        assert_eq!(reloaded.n_weighings, original.n_weighings);
+        assert_eq!(reloaded.weighings.len(), original.weighings.len());
+        assert_eq!(reloaded.guess, original.guess);
+        assert_eq!(reloaded.answer, original.answer);
+        assert_eq!(reloaded.result, original.result);
+    }
+}